@@ -0,0 +1,29 @@
+//! Lexing throughput on a large synthetic source. Run with
+//! `cargo bench -p asbel-compiler --bench lexer`.
+
+use asbel_compiler::parser::lexer;
+use chumsky::Parser;
+use std::time::Instant;
+
+const LINES: usize = 10_000;
+const RUNS: u32 = 10;
+
+fn main() {
+    let src: String = (0..LINES)
+        .map(|i| format!("let value_{i} = \"text {i}\"; // line {i}\nprint(value_{i} + {i});\n"))
+        .collect();
+
+    let lexer = lexer();
+    let mut tokens = 0;
+    let start = Instant::now();
+    for _ in 0..RUNS {
+        tokens = lexer.parse(src.as_str()).into_output().unwrap().len();
+    }
+    let per_run = start.elapsed() / RUNS;
+
+    let mb = src.len() as f64 / (1024.0 * 1024.0);
+    println!(
+        "lexed {tokens} tokens ({mb:.2} MiB) in {per_run:?} per run: {:.1} MiB/s",
+        mb / per_run.as_secs_f64()
+    );
+}
@@ -0,0 +1,131 @@
+use std::collections::HashMap;
+
+use crate::parser::Expr;
+use crate::visit::{walk_expr, Visitor};
+
+/// A non-fatal diagnostic. Expressions carry no spans yet, so the message
+/// names the offending binding instead.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Warning {
+    pub code: &'static str,
+    pub message: String,
+}
+
+pub const UNUSED_LET: &str = "W002";
+
+/// Reports `let` bindings that are never read in their scope, each when
+/// its scope closes, so inner bindings come first. Names starting with `_`
+/// are exempt.
+pub fn check(expr: &Expr) -> Vec<Warning> {
+    let mut unused = UnusedLets::default();
+    unused.visit_expr(expr);
+    unused.warnings
+}
+
+/// Tracks the bindings in scope during a single walk. `scopes` maps a name
+/// to the indices of its bindings in `used`, innermost last, so a read
+/// marks the binding it resolves to and shadowed ones stay untouched.
+#[derive(Default)]
+struct UnusedLets {
+    scopes: HashMap<String, Vec<usize>>,
+    used: Vec<bool>,
+    warnings: Vec<Warning>,
+}
+
+impl Visitor for UnusedLets {
+    fn visit_expr(&mut self, expr: &Expr) {
+        match expr {
+            Expr::Let(name, value, body) => {
+                // The initializer still sees the outer bindings.
+                self.visit_expr(value);
+
+                self.scopes
+                    .entry(name.clone())
+                    .or_default()
+                    .push(self.used.len());
+                self.used.push(false);
+                self.visit_expr(body);
+                self.scopes.get_mut(name).and_then(Vec::pop);
+                let used = self.used.pop() == Some(true);
+
+                if !name.starts_with('_') && !used {
+                    self.warnings.push(Warning {
+                        code: UNUSED_LET,
+                        message: format!("unused variable '{name}'"),
+                    });
+                }
+            }
+            Expr::Local(name) => {
+                if let Some(&binding) = self.scopes.get(name).and_then(|s| s.last()) {
+                    self.used[binding] = true;
+                }
+            }
+            _ => walk_expr(self, expr),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Op;
+    use crate::test_util::{binary, call, let_, local, num};
+
+    #[test]
+    fn use_nested_in_call_arguments_counts() {
+        // let x = 1; print(x + 1)
+        let expr = let_(
+            "x",
+            num(1.0),
+            call(local("print"), vec![binary(local("x"), Op::Add, num(1.0))]),
+        );
+
+        assert!(check(&expr).is_empty());
+    }
+
+    #[test]
+    fn unused_and_shadowed_bindings_warn() {
+        // let x = 1; let x = 2; x
+        let expr = let_("x", num(1.0), let_("x", num(2.0), local("x")));
+
+        assert_eq!(
+            check(&expr),
+            vec![Warning {
+                code: UNUSED_LET,
+                message: "unused variable 'x'".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn shadowing_initializer_reads_the_outer_binding() {
+        // let x = 1; let x = x + 1; x
+        let expr = let_(
+            "x",
+            num(1.0),
+            let_("x", binary(local("x"), Op::Add, num(1.0)), local("x")),
+        );
+
+        assert!(check(&expr).is_empty());
+    }
+
+    #[test]
+    fn underscore_names_are_exempt() {
+        let expr = let_("_unused", num(1.0), num(2.0));
+
+        assert!(check(&expr).is_empty());
+    }
+
+    #[test]
+    fn reports_each_unused_binding_when_its_scope_closes() {
+        // let a = 1; let b = 2; let c = a; 3
+        let expr = let_(
+            "a",
+            num(1.0),
+            let_("b", num(2.0), let_("c", local("a"), num(3.0))),
+        );
+        let messages: Vec<_> = check(&expr).into_iter().map(|w| w.message).collect();
+
+        assert_eq!(messages, ["unused variable 'c'", "unused variable 'b'"]);
+    }
+}
@@ -0,0 +1,216 @@
+use chumsky::Parser;
+
+use crate::parser::{lexer, Span, Token, TokenCategory};
+
+/// Coarse token classes for editor syntax highlighting.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum HighlightClass {
+    Keyword,
+    Identifier,
+    FunctionName,
+    Number,
+    String,
+    Comment,
+    Operator,
+    Punctuation,
+    Error,
+}
+
+/// Classifies `source` for highlighting. The spans are sorted, do not
+/// overlap, and cover every byte of the source except whitespace; the `\`
+/// of a line continuation is [`HighlightClass::Punctuation`]. Identifiers
+/// at a call (`f(`) or declaration (`fn f`) are reported as
+/// [`HighlightClass::FunctionName`].
+pub fn highlight(source: &str) -> Vec<(Span, HighlightClass)> {
+    let (tokens, errors) = lexer().parse(source).into_output_errors();
+    let tokens = tokens.unwrap_or_default();
+    let error_spans: Vec<Span> = errors.into_iter().map(|e| e.span).collect();
+
+    let mut spans: Vec<(Span, HighlightClass)> = tokens
+        .iter()
+        .enumerate()
+        .map(|(i, (_, span))| {
+            let class = if error_spans.contains(span) {
+                HighlightClass::Error
+            } else {
+                classify(&tokens, i)
+            };
+            (span.clone(), class)
+        })
+        .collect();
+    // Unexpected characters and unterminated block comments have no token.
+    spans.extend(
+        error_spans
+            .into_iter()
+            .filter(|error| !tokens.iter().any(|(_, span)| span == error))
+            .map(|span| (span, HighlightClass::Error)),
+    );
+    spans.sort_by_key(|(span, _)| span.start);
+
+    let mut highlighted = Vec::with_capacity(spans.len());
+    let mut end = 0;
+    for (span, class) in spans {
+        push_trivia(source, end..span.start, &mut highlighted);
+        end = span.end;
+        highlighted.push((span, class));
+    }
+    push_trivia(source, end..source.len(), &mut highlighted);
+    highlighted
+}
+
+fn classify(tokens: &[(Token, Span)], i: usize) -> HighlightClass {
+    match tokens[i].0.category() {
+        TokenCategory::Ident => {
+            let called = matches!(tokens.get(i + 1), Some((Token::Ctrl('('), _)));
+            let declared = i > 0 && tokens[i - 1].0 == Token::Fn;
+            if called || declared {
+                HighlightClass::FunctionName
+            } else {
+                HighlightClass::Identifier
+            }
+        }
+        TokenCategory::Keyword(_) | TokenCategory::Bool | TokenCategory::Null => {
+            HighlightClass::Keyword
+        }
+        TokenCategory::Num => HighlightClass::Number,
+        TokenCategory::Str => HighlightClass::String,
+        TokenCategory::DocComment => HighlightClass::Comment,
+        TokenCategory::Op => HighlightClass::Operator,
+        TokenCategory::Ctrl => HighlightClass::Punctuation,
+    }
+}
+
+/// Comments and continuations are trivia to the lexer, so they are
+/// recovered from the gaps between tokens, which hold nothing else besides
+/// whitespace.
+fn push_trivia(source: &str, gap: Span, highlighted: &mut Vec<(Span, HighlightClass)>) {
+    let text = &source[gap.clone()];
+    let mut i = 0;
+    while let Some(c) = text[i..].chars().next() {
+        let rest = &text[i..];
+        let (len, class) = if rest.starts_with("//") {
+            let len = rest.find('\n').unwrap_or(rest.len());
+            (len, HighlightClass::Comment)
+        } else if rest.starts_with("/*") {
+            (block_comment_len(rest), HighlightClass::Comment)
+        } else if c == '\\' {
+            (1, HighlightClass::Punctuation)
+        } else {
+            i += c.len_utf8();
+            continue;
+        };
+        let start = gap.start + i;
+        highlighted.push((start..start + len, class));
+        i += len;
+    }
+}
+
+/// Length of the nested block comment at the start of `text`, or all of
+/// `text` if it is never closed.
+fn block_comment_len(text: &str) -> usize {
+    let mut depth = 0;
+    let mut i = 0;
+    while let Some(c) = text[i..].chars().next() {
+        if text[i..].starts_with("/*") {
+            depth += 1;
+            i += 2;
+        } else if text[i..].starts_with("*/") {
+            depth -= 1;
+            i += 2;
+            if depth == 0 {
+                return i;
+            }
+        } else {
+            i += c.len_utf8();
+        }
+    }
+    text.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn classes(source: &str) -> Vec<(&str, HighlightClass)> {
+        highlight(source)
+            .into_iter()
+            .map(|(span, class)| (&source[span], class))
+            .collect()
+    }
+
+    #[test]
+    fn classifies_a_representative_snippet() {
+        use HighlightClass::*;
+
+        let src =
+            "/// Doubles n.\nfn double(n) { n * 2 } // twice\nlet s = /* x */ \"hi\"; print(true)";
+        assert_eq!(
+            classes(src),
+            vec![
+                ("/// Doubles n.", Comment),
+                ("fn", Keyword),
+                ("double", FunctionName),
+                ("(", Punctuation),
+                ("n", Identifier),
+                (")", Punctuation),
+                ("{", Punctuation),
+                ("n", Identifier),
+                ("*", Operator),
+                ("2", Number),
+                ("}", Punctuation),
+                ("// twice", Comment),
+                ("let", Keyword),
+                ("s", Identifier),
+                ("=", Operator),
+                ("/* x */", Comment),
+                ("\"hi\"", String),
+                (";", Punctuation),
+                ("print", FunctionName),
+                ("(", Punctuation),
+                ("true", Keyword),
+                (")", Punctuation),
+            ]
+        );
+    }
+
+    #[test]
+    fn errors_and_continuations_are_highlighted_in_place() {
+        use HighlightClass::*;
+
+        assert_eq!(
+            classes("a @ \"open"),
+            vec![("a", Identifier), ("@", Error), ("\"open", Error)]
+        );
+        assert_eq!(
+            classes("x \\\n y"),
+            vec![("x", Identifier), ("\\", Punctuation), ("y", Identifier)]
+        );
+        assert_eq!(
+            classes("x /* /* */ never closed"),
+            vec![("x", Identifier), ("/* /* */ never closed", Error)]
+        );
+    }
+
+    #[test]
+    fn spans_are_ordered_disjoint_and_cover_all_but_whitespace() {
+        let inputs = [
+            "let x = 1 + \\\n  2 // done\r\n/* a /* b */ c */ f(x)",
+            "é@ \"s\" r#\"q\"# \"\"\"\nmulti\n\"\"\" 1..=3 /// doc",
+            "fn f() { g() } /* unterminated",
+        ];
+
+        for src in inputs {
+            let mut covered = vec![false; src.len()];
+            let mut end = 0;
+            for (span, _) in highlight(src) {
+                assert!(span.start >= end && span.start < span.end, "{src:?}");
+                covered[span.clone()].iter_mut().for_each(|b| *b = true);
+                end = span.end;
+            }
+            for (i, c) in src.char_indices() {
+                assert!(covered[i] || c.is_whitespace(), "{c:?} at {i} in {src:?}");
+            }
+        }
+    }
+}
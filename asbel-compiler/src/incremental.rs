@@ -0,0 +1,199 @@
+use std::ops::Range;
+
+use chumsky::Parser;
+
+use crate::parser::{lexer, Span, Token};
+
+/// Replacement of the bytes in `range` of the old source with `new_text`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TextEdit {
+    pub range: Range<usize>,
+    pub new_text: String,
+}
+
+/// The furthest the lexer looks past the end of a token to decide it, in
+/// bytes (`r#"`, `1..`, `"""`, each up to four bytes per char).
+const LOOKAHEAD: usize = 12;
+
+/// Relexes `source`, the text after applying `edit` to the source that
+/// `old_tokens` were lexed from, reusing the old tokens outside the edit.
+///
+/// Lexing restarts at the end of the last token that ends before the
+/// edited line and stops as soon as it produces a token starting where a
+/// shifted old token after the edit starts; the lexer keeps no state
+/// between tokens, so from there on the old tokens are still correct. Lex
+/// errors are not reported; run the full [`lexer`] for diagnostics.
+pub fn relex(
+    source: &str,
+    old_tokens: &[(Token<'_>, Span)],
+    edit: &TextEdit,
+) -> Vec<(Token<'static>, Span)> {
+    let delta = edit.new_text.len() as isize - edit.range.len() as isize;
+    let shift = |offset: usize| (offset as isize + delta) as usize;
+
+    let line_start = source[..edit.range.start]
+        .rfind('\n')
+        .map_or(0, |newline| newline + 1);
+    let kept = old_tokens.partition_point(|(_, span)| span.end < line_start);
+    let restart = kept.checked_sub(1).map_or(0, |last| old_tokens[last].1.end);
+    // Old tokens from `tail` on start after the edit, so their text is
+    // unchanged and only their offsets move.
+    let tail = old_tokens.partition_point(|(_, span)| span.start < edit.range.end);
+
+    let mut relexed: Vec<(Token<'static>, Span)> = old_tokens[..kept]
+        .iter()
+        .map(|(token, span)| (token.clone().into_owned(), span.clone()))
+        .collect();
+
+    let mut window = 4;
+    loop {
+        let stop = old_tokens
+            .get(tail + window)
+            .map_or(source.len(), |(_, span)| shift(span.start));
+        let at_end = stop == source.len();
+        let tokens = lexer()
+            .parse(&source[restart..stop])
+            .into_output()
+            .unwrap_or_default();
+
+        for (token, span) in tokens {
+            let span = span.start + restart..span.end + restart;
+            let settled = at_end || span.end + LOOKAHEAD <= stop;
+            if settled {
+                let old_start = span.start as isize - delta;
+                if let Ok(i) = old_tokens[tail..]
+                    .binary_search_by_key(&old_start, |(_, span)| span.start as isize)
+                {
+                    relexed.extend(old_tokens[tail + i..].iter().map(|(token, span)| {
+                        (
+                            token.clone().into_owned(),
+                            shift(span.start)..shift(span.end),
+                        )
+                    }));
+                    return relexed;
+                }
+            } else if !at_end {
+                break;
+            }
+            relexed.push((token.into_owned(), span));
+        }
+
+        if at_end {
+            return relexed;
+        }
+        relexed.truncate(kept);
+        window *= 2;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lex_owned(source: &str) -> Vec<(Token<'static>, Span)> {
+        lexer()
+            .parse(source)
+            .into_output()
+            .unwrap()
+            .into_iter()
+            .map(|(token, span)| (token.into_owned(), span))
+            .collect()
+    }
+
+    fn apply(source: &str, edit: &TextEdit) -> String {
+        let mut edited = source.to_string();
+        edited.replace_range(edit.range.clone(), &edit.new_text);
+        edited
+    }
+
+    fn assert_matches_full_lex(source: &str, edit: TextEdit) {
+        let edited = apply(source, &edit);
+        assert_eq!(
+            relex(&edited, &lex_owned(source), &edit),
+            lex_owned(&edited),
+            "{edit:?} on {source:?}"
+        );
+    }
+
+    #[test]
+    fn relex_shifts_tokens_after_an_edit() {
+        let source = "let a = 1\nlet b = a + 2\nprint(b)";
+        let edit = TextEdit {
+            range: 14..15,
+            new_text: "longer".to_string(),
+        };
+        let edited = apply(source, &edit);
+
+        assert_eq!(edited, "let a = 1\nlet longer = a + 2\nprint(b)");
+        assert_eq!(
+            relex(&edited, &lex_owned(source), &edit),
+            lex_owned(&edited)
+        );
+    }
+
+    #[test]
+    fn relex_handles_edits_that_open_and_close_multi_line_tokens() {
+        let source = "x = 1\n/* note */\ny = \"\"\"a\nb\"\"\" z\nw";
+        // Opening a comment swallows the rest of the file.
+        assert_matches_full_lex(
+            source,
+            TextEdit {
+                range: 0..0,
+                new_text: "/*".to_string(),
+            },
+        );
+        // Editing inside a multi-line string restarts before it.
+        assert_matches_full_lex(
+            source,
+            TextEdit {
+                range: 25..26,
+                new_text: "c\n\"\"\" q \"\"\"".to_string(),
+            },
+        );
+        // Deleting the closing `*/` turns the following lines into comment.
+        assert_matches_full_lex(
+            source,
+            TextEdit {
+                range: 14..16,
+                new_text: String::new(),
+            },
+        );
+    }
+
+    #[test]
+    fn relex_agrees_with_a_full_lex_for_random_edits() {
+        let source = "/// doc\nfn f(a, b) { a ** b % 3 }\nlet s = r#\"q\"# + \"x\" // c\n\
+                      for i in 0..=10 { f(i, 1.5) } /* a /* b */ */\nconst t = \"\"\"\nm\n\"\"\"";
+        let fragments = [
+            "", " ", "\n", "a", "1", ".", "=", "\"", "\"\"\"", "/*", "*/", "//", "r#", "é", "\\\n",
+        ];
+
+        // A fixed linear congruential generator keeps failures reproducible.
+        let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+        let mut next = |bound: usize| {
+            state = state
+                .wrapping_mul(6_364_136_223_846_793_005)
+                .wrapping_add(1_442_695_040_888_963_407);
+            (state >> 33) as usize % bound
+        };
+
+        let mut text = source.to_string();
+        for _ in 0..500 {
+            let boundaries: Vec<usize> = (0..=text.len())
+                .filter(|&i| text.is_char_boundary(i))
+                .collect();
+            let a = boundaries[next(boundaries.len())];
+            let b = boundaries[next(boundaries.len())];
+            let edit = TextEdit {
+                range: a.min(b)..a.max(b).min(a.min(b) + 8),
+                new_text: fragments[next(fragments.len())].to_string(),
+            };
+            if !text.is_char_boundary(edit.range.end) {
+                continue;
+            }
+
+            assert_matches_full_lex(&text, edit.clone());
+            text = apply(&text, &edit);
+        }
+    }
+}
@@ -0,0 +1,144 @@
+use crate::parser::Span;
+
+/// A piece of an interpolated string literal such as `"x is {x}"`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum StringPart<'a> {
+    Literal(String),
+    /// Source text of an embedded expression, with its byte span inside the
+    /// literal's contents, so the parser can shift positions back into the
+    /// original file when it parses the expression.
+    Expr(&'a str, Span),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum InterpolationError {
+    /// A `{` with no matching `}`, at the offset of the `{`.
+    Unclosed(usize),
+    /// A lone `}` that is not part of a `}}` escape.
+    UnmatchedClose(usize),
+    /// `{}` with nothing inside, at the offset of the `{`.
+    Empty(usize),
+}
+
+impl std::fmt::Display for InterpolationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            InterpolationError::Unclosed(at) => write!(f, "unclosed '{{' at offset {}", at),
+            InterpolationError::UnmatchedClose(at) => {
+                write!(
+                    f,
+                    "unmatched '}}' at offset {}, write '}}}}' for a literal brace",
+                    at
+                )
+            }
+            InterpolationError::Empty(at) => write!(f, "empty interpolation at offset {}", at),
+        }
+    }
+}
+
+/// Splits the contents of a string literal into literal text and embedded
+/// `{expression}` source. `{{` and `}}` stand for literal braces, and braces
+/// nested inside an expression are balanced, so `{f({a})}` is one part.
+pub fn split(contents: &str) -> Result<Vec<StringPart<'_>>, InterpolationError> {
+    let mut parts = Vec::new();
+    let mut literal = String::new();
+    let mut chars = contents.char_indices().peekable();
+
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '{' if chars.peek().map(|&(_, c)| c) == Some('{') => {
+                chars.next();
+                literal.push('{');
+            }
+            '}' if chars.peek().map(|&(_, c)| c) == Some('}') => {
+                chars.next();
+                literal.push('}');
+            }
+            '}' => return Err(InterpolationError::UnmatchedClose(i)),
+            '{' => {
+                let start = i + 1;
+                let mut depth = 1;
+                let end = loop {
+                    match chars.next() {
+                        Some((_, '{')) => depth += 1,
+                        Some((j, '}')) => {
+                            depth -= 1;
+                            if depth == 0 {
+                                break j;
+                            }
+                        }
+                        Some(_) => {}
+                        None => return Err(InterpolationError::Unclosed(i)),
+                    }
+                };
+                if contents[start..end].trim().is_empty() {
+                    return Err(InterpolationError::Empty(i));
+                }
+                if !literal.is_empty() {
+                    parts.push(StringPart::Literal(std::mem::take(&mut literal)));
+                }
+                parts.push(StringPart::Expr(&contents[start..end], start..end));
+            }
+            c => literal.push(c),
+        }
+    }
+
+    if !literal.is_empty() {
+        parts.push(StringPart::Literal(literal));
+    }
+    Ok(parts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_literals_and_expressions_with_spans() {
+        assert_eq!(
+            split("x is {x} and sum is {a + b}"),
+            Ok(vec![
+                StringPart::Literal("x is ".to_string()),
+                StringPart::Expr("x", 6..7),
+                StringPart::Literal(" and sum is ".to_string()),
+                StringPart::Expr("a + b", 21..26),
+            ])
+        );
+    }
+
+    #[test]
+    fn nested_braces_and_calls_stay_in_one_expression() {
+        assert_eq!(
+            split("{f(1, 2)}{ {a} }"),
+            Ok(vec![
+                StringPart::Expr("f(1, 2)", 1..8),
+                StringPart::Expr(" {a} ", 10..15),
+            ])
+        );
+    }
+
+    #[test]
+    fn doubled_braces_are_literal() {
+        assert_eq!(
+            split("{{not}} {it}"),
+            Ok(vec![
+                StringPart::Literal("{not} ".to_string()),
+                StringPart::Expr("it", 9..11),
+            ])
+        );
+        assert_eq!(split(""), Ok(vec![]));
+    }
+
+    #[test]
+    fn malformed_interpolations_report_their_position() {
+        assert_eq!(split("ab {x"), Err(InterpolationError::Unclosed(3)));
+        assert_eq!(split("ab } c"), Err(InterpolationError::UnmatchedClose(3)));
+        assert_eq!(split("a { } b"), Err(InterpolationError::Empty(2)));
+        assert_eq!(
+            InterpolationError::UnmatchedClose(3).to_string(),
+            "unmatched '}' at offset 3, write '}}' for a literal brace"
+        );
+    }
+}
@@ -1,16 +1,31 @@
-pub fn add(left: u64, right: u64) -> u64 {
-    left + right
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn it_works() {
-        let result = add(2, 2);
-        assert_eq!(result, 4);
-    }
-}
+//! The ASBEL compiler front end.
+//!
+//! The stable surface is the [`parser`] module: [`parser::lexer`] and its
+//! [`parser::LexError`]s, the [`parser::Token`] stream it produces with its
+//! [`parser::Span`]s, and the AST node types ([`parser::Expr`],
+//! [`parser::Val`], [`parser::Op`]). The enums are `#[non_exhaustive]`
+//! because the language is still growing, so downstream matches need a
+//! wildcard arm. [`source::LineIndex`] maps spans back to line numbers and
+//! source text for diagnostics.
+//!
+//! Around it sit the passes and editor helpers: [`visit`] for walking and
+//! rewriting a [`parser::Expr`], [`optimizer::fold_constants`],
+//! [`analysis::check`] for warnings, [`sexpr::to_sexpr`] for AST dumps,
+//! [`interpolation::split`] for string interpolation,
+//! [`highlight::highlight`] for syntax highlighting and
+//! [`incremental::relex`] for relexing after an edit. Everything else is
+//! `pub(crate)`. The full public item list is pinned in
+//! `tests/public_api.txt`, so any change to it shows up in review.
 
+pub mod analysis;
+pub mod highlight;
+pub mod incremental;
+pub mod interpolation;
+pub mod optimizer;
 pub mod parser;
+pub mod sexpr;
+pub mod source;
+pub mod visit;
+
+#[cfg(test)]
+mod test_util;
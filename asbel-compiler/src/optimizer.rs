@@ -0,0 +1,173 @@
+use crate::parser::{Expr, Op, Val};
+use crate::visit::{fold_children, Folder};
+
+/// Rewrites binary expressions whose operands are literals into a single
+/// literal, recursing through every sub-expression.
+///
+/// Numbers are `f64`, so folding uses exactly the arithmetic evaluation
+/// would. A fold whose result is not finite (overflow to infinity, `0 / 0`,
+/// division by zero) is left unfolded so the runtime decides what it means.
+/// `==` and `!=` fold only between literals of the same type; comparing
+/// across types is likewise left to the runtime.
+pub fn fold_constants(expr: Expr) -> Expr {
+    ConstantFolder.fold_expr(expr)
+}
+
+/// The [`Folder`] behind [`fold_constants`].
+pub(crate) struct ConstantFolder;
+
+impl Folder for ConstantFolder {
+    fn fold_expr(&mut self, expr: Expr) -> Expr {
+        match fold_children(self, expr) {
+            Expr::Binary(lhs, op, rhs) => match (*lhs, *rhs) {
+                (Expr::Value(a), Expr::Value(b)) => match fold_binary(&a, op, &b) {
+                    Some(val) => Expr::Value(val),
+                    None => binary(Expr::Value(a), op, Expr::Value(b)),
+                },
+                (lhs, rhs) => binary(lhs, op, rhs),
+            },
+            expr => expr,
+        }
+    }
+}
+
+fn binary(lhs: Expr, op: Op, rhs: Expr) -> Expr {
+    Expr::Binary(Box::new(lhs), op, Box::new(rhs))
+}
+
+fn fold_binary(a: &Val, op: Op, b: &Val) -> Option<Val> {
+    let folded = match (a, op, b) {
+        (a, Op::Eq | Op::NotEq, b) => Val::Bool(literal_eq(a, b)? == (op == Op::Eq)),
+        (Val::Bool(a), Op::And, Val::Bool(b)) => Val::Bool(*a && *b),
+        (Val::Bool(a), Op::Or, Val::Bool(b)) => Val::Bool(*a || *b),
+        (Val::Str(a), Op::Add, Val::Str(b)) => Val::Str(format!("{a}{b}")),
+        (Val::Num(a), op, Val::Num(b)) => {
+            let n = match op {
+                Op::Add => a + b,
+                Op::Sub => a - b,
+                Op::Mul => a * b,
+                Op::Div => a / b,
+                Op::Rem => a % b,
+                Op::Pow => a.powf(*b),
+                _ => return None,
+            };
+            if !n.is_finite() {
+                return None;
+            }
+            Val::Num(n)
+        }
+        _ => return None,
+    };
+    Some(folded)
+}
+
+/// Equality of two literals of the same type, or `None` for any other pair.
+fn literal_eq(a: &Val, b: &Val) -> Option<bool> {
+    match (a, b) {
+        (Val::Num(a), Val::Num(b)) => Some(a == b),
+        (Val::Str(a), Val::Str(b)) => Some(a == b),
+        (Val::Bool(a), Val::Bool(b)) => Some(a == b),
+        (Val::Null, Val::Null) => Some(true),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{call, let_, local, num};
+
+    #[test]
+    fn folds_nested_arithmetic() {
+        // 1 + 2 * 3
+        let expr = binary(num(1.0), Op::Add, binary(num(2.0), Op::Mul, num(3.0)));
+
+        assert_eq!(fold_constants(expr), num(7.0));
+    }
+
+    #[test]
+    fn folds_inside_calls_and_lets_but_keeps_variables() {
+        // let x = 4 * 1024; print(x + (1 + 1))
+        let expr = let_(
+            "x",
+            binary(num(4.0), Op::Mul, num(1024.0)),
+            call(
+                local("print"),
+                vec![binary(
+                    local("x"),
+                    Op::Add,
+                    binary(num(1.0), Op::Add, num(1.0)),
+                )],
+            ),
+        );
+
+        assert_eq!(
+            fold_constants(expr),
+            let_(
+                "x",
+                num(4096.0),
+                call(local("print"), vec![binary(local("x"), Op::Add, num(2.0))]),
+            )
+        );
+    }
+
+    #[test]
+    fn folds_large_integer_literals_like_evaluation_would() {
+        let expr = binary(num(9223372036854775807.0), Op::Add, num(1.0));
+
+        // i64::MAX is not representable as f64 and rounds to 2^63 already.
+        assert_eq!(fold_constants(expr), num(9223372036854775808.0));
+    }
+
+    #[test]
+    fn leaves_non_finite_results_unfolded() {
+        let overflow = binary(num(f64::MAX), Op::Mul, num(2.0));
+        let div_by_zero = binary(num(1.0), Op::Div, num(0.0));
+
+        assert_eq!(fold_constants(overflow.clone()), overflow);
+        assert_eq!(fold_constants(div_by_zero.clone()), div_by_zero);
+    }
+
+    #[test]
+    fn folds_comparisons_strings_and_logic() {
+        let eq = binary(num(1.0), Op::Eq, num(1.0));
+        let concat = binary(
+            Expr::Value(Val::Str("ab".to_string())),
+            Op::Add,
+            Expr::Value(Val::Str("cd".to_string())),
+        );
+        let and = binary(
+            Expr::Value(Val::Bool(true)),
+            Op::And,
+            Expr::Value(Val::Bool(false)),
+        );
+
+        assert_eq!(fold_constants(eq), Expr::Value(Val::Bool(true)));
+        assert_eq!(
+            fold_constants(concat),
+            Expr::Value(Val::Str("abcd".to_string()))
+        );
+        assert_eq!(fold_constants(and), Expr::Value(Val::Bool(false)));
+    }
+
+    #[test]
+    fn folds_equality_only_between_same_type_literals() {
+        let str_ = |s: &str| Expr::Value(Val::Str(s.to_string()));
+        let func = |s: &str| Expr::Value(Val::Func(s.to_string()));
+        let null = Expr::Value(Val::Null);
+
+        assert_eq!(
+            fold_constants(binary(str_("a"), Op::NotEq, str_("b"))),
+            Expr::Value(Val::Bool(true))
+        );
+        assert_eq!(
+            fold_constants(binary(null.clone(), Op::Eq, null)),
+            Expr::Value(Val::Bool(true))
+        );
+
+        let mixed = binary(num(1.0), Op::Eq, str_("1"));
+        let funcs = binary(func("f"), Op::Eq, func("f"));
+        assert_eq!(fold_constants(mixed.clone()), mixed);
+        assert_eq!(fold_constants(funcs.clone()), funcs);
+    }
+}
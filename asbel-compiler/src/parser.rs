@@ -3,12 +3,13 @@
 // adapted for the ASBEL language.
 // Original source: https://github.com/zesterer/chumsky/blob/master/examples/nano_rust.rs
 
-use chumsky::prelude::*;
-use std::collections::HashMap;
+use chumsky::{label::LabelError, prelude::*, util::MaybeRef};
+use std::borrow::Cow;
 
 pub type Span = std::ops::Range<usize>;
 
 #[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
 pub enum Val {
     Null,
     Bool(bool),
@@ -18,6 +19,7 @@ pub enum Val {
 }
 
 #[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
 pub enum Expr {
     Error,
     Value(Val),
@@ -30,22 +32,86 @@ pub enum Expr {
     If(Box<Self>, Box<Self>, Box<Self>),
 }
 
+/// A lexed token. Text-carrying variants borrow from the source being
+/// lexed; use [`Token::into_owned`] to detach a token from it.
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
-pub enum Token {
+#[non_exhaustive]
+pub enum Token<'src> {
     Null,
     Bool(bool),
-    Num(String),
-    Str(String),
-    Op(String),
+    Num(Cow<'src, str>),
+    Str(Cow<'src, str>),
+    Op(Cow<'src, str>),
     Ctrl(char),
-    Ident(String),
+    Ident(Cow<'src, str>),
+    DocComment(Cow<'src, str>),
     Fn,
     Let,
+    Struct,
     If,
     Else,
+    For,
+    In,
+    Mut,
+    Const,
+    Import,
+    Enum,
+    Match,
+}
+
+impl Token<'_> {
+    pub fn into_owned(self) -> Token<'static> {
+        match self {
+            Token::Null => Token::Null,
+            Token::Bool(x) => Token::Bool(x),
+            Token::Num(n) => Token::Num(Cow::Owned(n.into_owned())),
+            Token::Str(s) => Token::Str(Cow::Owned(s.into_owned())),
+            Token::Op(s) => Token::Op(Cow::Owned(s.into_owned())),
+            Token::Ctrl(c) => Token::Ctrl(c),
+            Token::Ident(s) => Token::Ident(Cow::Owned(s.into_owned())),
+            Token::DocComment(s) => Token::DocComment(Cow::Owned(s.into_owned())),
+            Token::Fn => Token::Fn,
+            Token::Let => Token::Let,
+            Token::Struct => Token::Struct,
+            Token::If => Token::If,
+            Token::Else => Token::Else,
+            Token::For => Token::For,
+            Token::In => Token::In,
+            Token::Mut => Token::Mut,
+            Token::Const => Token::Const,
+            Token::Import => Token::Import,
+            Token::Enum => Token::Enum,
+            Token::Match => Token::Match,
+        }
+    }
+
+    pub fn category(&self) -> TokenCategory {
+        match self {
+            Token::Null => TokenCategory::Null,
+            Token::Bool(_) => TokenCategory::Bool,
+            Token::Num(_) => TokenCategory::Num,
+            Token::Str(_) => TokenCategory::Str,
+            Token::Op(_) => TokenCategory::Op,
+            Token::Ctrl(_) => TokenCategory::Ctrl,
+            Token::Ident(_) => TokenCategory::Ident,
+            Token::DocComment(_) => TokenCategory::DocComment,
+            Token::Fn => TokenCategory::Keyword("fn"),
+            Token::Let => TokenCategory::Keyword("let"),
+            Token::Struct => TokenCategory::Keyword("struct"),
+            Token::If => TokenCategory::Keyword("if"),
+            Token::Else => TokenCategory::Keyword("else"),
+            Token::For => TokenCategory::Keyword("for"),
+            Token::In => TokenCategory::Keyword("in"),
+            Token::Mut => TokenCategory::Keyword("mut"),
+            Token::Const => TokenCategory::Keyword("const"),
+            Token::Import => TokenCategory::Keyword("import"),
+            Token::Enum => TokenCategory::Keyword("enum"),
+            Token::Match => TokenCategory::Keyword("match"),
+        }
+    }
 }
 
-impl std::fmt::Display for Token {
+impl std::fmt::Display for Token<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match self {
             Token::Null => write!(f, "null"),
@@ -55,93 +121,896 @@ impl std::fmt::Display for Token {
             Token::Op(s) => write!(f, "{}", s),
             Token::Ctrl(c) => write!(f, "{}", c),
             Token::Ident(s) => write!(f, "{}", s),
+            Token::DocComment(s) => write!(f, "///{}", s),
             Token::Fn => write!(f, "fn"),
             Token::Let => write!(f, "let"),
+            Token::Struct => write!(f, "struct"),
             Token::If => write!(f, "if"),
             Token::Else => write!(f, "else"),
+            Token::For => write!(f, "for"),
+            Token::In => write!(f, "in"),
+            Token::Mut => write!(f, "mut"),
+            Token::Const => write!(f, "const"),
+            Token::Import => write!(f, "import"),
+            Token::Enum => write!(f, "enum"),
+            Token::Match => write!(f, "match"),
+        }
+    }
+}
+
+/// The kind of a token without its payload, for saying what was expected
+/// ("expected an identifier") without building a placeholder token.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum TokenCategory {
+    Null,
+    Bool,
+    Num,
+    Str,
+    Op,
+    Ctrl,
+    Ident,
+    DocComment,
+    Keyword(&'static str),
+}
+
+impl std::fmt::Display for TokenCategory {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            TokenCategory::Null => write!(f, "'null'"),
+            TokenCategory::Bool => write!(f, "a boolean literal"),
+            TokenCategory::Num => write!(f, "a number literal"),
+            TokenCategory::Str => write!(f, "a string literal"),
+            TokenCategory::Op => write!(f, "an operator"),
+            TokenCategory::Ctrl => write!(f, "punctuation"),
+            TokenCategory::Ident => write!(f, "an identifier"),
+            TokenCategory::DocComment => write!(f, "a doc comment"),
+            TokenCategory::Keyword(k) => write!(f, "keyword '{}'", k),
+        }
+    }
+}
+
+/// What went wrong while lexing, independent of where it happened.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum LexErrorKind {
+    UnexpectedChar(char),
+    /// Only a fallback for the [`LabelError`] impl, which chumsky calls when
+    /// a parser runs out of input. The lexer's one-character recovery arm
+    /// accepts any input, so [`lexer`] never reports this in practice.
+    UnexpectedEnd,
+    UnterminatedString,
+    UnterminatedBlockComment,
+}
+
+impl std::fmt::Display for LexErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            LexErrorKind::UnexpectedChar(c) => write!(f, "unexpected character {:?}", c),
+            LexErrorKind::UnexpectedEnd => write!(f, "unexpected end of input"),
+            LexErrorKind::UnterminatedString => write!(f, "unterminated string literal"),
+            LexErrorKind::UnterminatedBlockComment => {
+                write!(f, "unterminated block comment, expected `*/`")
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LexError {
+    pub kind: LexErrorKind,
+    pub span: Span,
+}
+
+impl std::fmt::Display for LexError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{} at {}..{}", self.kind, self.span.start, self.span.end)
+    }
+}
+
+impl<'a> chumsky::error::Error<'a, &'a str> for LexError {}
+
+impl<'a, L> LabelError<'a, &'a str, L> for LexError {
+    fn expected_found<E: IntoIterator<Item = L>>(
+        _expected: E,
+        found: Option<MaybeRef<'a, char>>,
+        span: SimpleSpan,
+    ) -> Self {
+        let kind = match found {
+            Some(c) => LexErrorKind::UnexpectedChar(*c),
+            None => LexErrorKind::UnexpectedEnd,
+        };
+        LexError {
+            kind,
+            span: span.into_range(),
         }
     }
 }
 
-pub fn lexer<'a>() -> impl Parser<'a, &'a str, Vec<(Token, Span)>, extra::Err<Simple<'a, char>>> {
+fn lex_error(kind: LexErrorKind, span: SimpleSpan) -> LexError {
+    LexError {
+        kind,
+        span: span.into_range(),
+    }
+}
+
+pub fn lexer<'a>() -> impl Parser<'a, &'a str, Vec<(Token<'a>, Span)>, extra::Err<LexError>> {
     let num = text::int(10)
         .then(just('.').then(text::digits(10)).or_not())
         .to_slice()
-        .map(|s: &str| Token::Num(s.to_string()));
+        .map(|s: &str| Token::Num(s.into()));
+
+    // `"""..."""` may span lines and contain lone quotes. Its contents are
+    // kept verbatim, indentation included.
+    let triple_str = just("\"\"\"")
+        .ignore_then(any().and_is(just("\"\"\"").not()).repeated().to_slice())
+        .then(just("\"\"\"").to(true).or(end().to(false)))
+        .validate(|(s, terminated): (&str, bool), e, emitter| {
+            if !terminated {
+                emitter.emit(lex_error(LexErrorKind::UnterminatedString, e.span()));
+            }
+            Token::Str(s.into())
+        });
 
     let str_ = just('"')
         .ignore_then(none_of('"').repeated().to_slice())
-        .then_ignore(just('"'))
-        .map(|s: &str| Token::Str(s.to_string()));
+        .then(just('"').to(true).or(end().to(false)))
+        .validate(|(s, terminated): (&str, bool), e, emitter| {
+            if !terminated {
+                emitter.emit(lex_error(LexErrorKind::UnterminatedString, e.span()));
+            }
+            Token::Str(s.into())
+        });
 
-    let op = one_of("+-*/!=".chars())
-        .repeated()
-        .at_least(1)
-        .to_slice()
-        .map(|s: &str| Token::Op(s.to_string()));
+    // `r"..."` is a raw string; `r#"..."#` may also contain `"`. Strings have
+    // no escapes yet, so raw strings differ only in what can end them, and
+    // both produce the same Str token.
+    let raw_str = just('r')
+        .ignore_then(
+            just("#\"")
+                .ignore_then(any().and_is(just("\"#").not()).repeated().to_slice())
+                .then(just("\"#").to(true).or(end().to(false)))
+                .or(just('"')
+                    .ignore_then(none_of('"').repeated().to_slice())
+                    .then(just('"').to(true).or(end().to(false)))),
+        )
+        .validate(|(s, terminated): (&str, bool), e, emitter| {
+            if !terminated {
+                emitter.emit(lex_error(LexErrorKind::UnterminatedString, e.span()));
+            }
+            Token::Str(s.into())
+        });
+
+    // Operators are a fixed set matched longest first, not a run of operator
+    // characters, so `a&&!b` is `&&` then `!` and `2**-1` is `**` then `-`.
+    // `=>` separates a match arm's pattern from its body. A `/` that starts
+    // a `//` or `/*` comment is never an operator.
+    let op = choice((
+        just("**"),
+        just("&&"),
+        just("||"),
+        just("=="),
+        just("!="),
+        just("=>"),
+    ))
+    .or(one_of("+-*/%!=&|").to_slice())
+    .and_is(just("//").or(just("/*")).not())
+    .map(|s: &str| Token::Op(s.into()));
 
-    let ctrl = one_of("()[],.;".chars()).map(|c| Token::Ctrl(c));
+    // Checked before `ctrl` so `..` and `..=` are not split into single
+    // `.` tokens; a lone `.` stays Ctrl('.') for member access.
+    let range = just("..=")
+        .or(just(".."))
+        .map(|s: &str| Token::Op(s.into()));
+
+    let ctrl = one_of("()[]{},.:;").map(Token::Ctrl);
 
     let ident = text::ident().map(|s: &str| match s {
         "fn" => Token::Fn,
         "let" => Token::Let,
+        "struct" => Token::Struct,
         "if" => Token::If,
         "else" => Token::Else,
+        "for" => Token::For,
+        "in" => Token::In,
+        "mut" => Token::Mut,
+        "const" => Token::Const,
+        "import" => Token::Import,
+        "enum" => Token::Enum,
+        "match" => Token::Match,
         "true" => Token::Bool(true),
         "false" => Token::Bool(false),
         "null" => Token::Null,
-        _ => Token::Ident(s.to_string()),
+        _ => Token::Ident(s.into()),
     });
 
-    let token = num
+    // `///` starts a doc comment, kept as a token so it can be attached to
+    // the declaration that follows; `////` and longer stay plain comments.
+    let doc_comment = just("///")
+        .and_is(just("////").not())
+        .ignore_then(any().and_is(just('\n').not()).repeated().to_slice())
+        .map(|s: &str| Token::DocComment(s.trim_end().into()));
+
+    let token = doc_comment
+        .or(num)
+        .or(triple_str)
         .or(str_)
+        .or(raw_str)
         .or(op)
+        .or(range)
         .or(ctrl)
-        .or(ident)
-        .recover_with(skip_then_retry_until(
-            any().ignored(),
-            one_of(" \r\n".chars()).ignored(),
-        ));
+        .or(ident);
+
+    // A character that starts no token is reported on its own span and
+    // skipped, so the rest of the input still lexes.
+    let unexpected = any().validate(|c, e, emitter| {
+        emitter.emit(lex_error(LexErrorKind::UnexpectedChar(c), e.span()));
+    });
+
+    let line_comment = just("//")
+        .and_is(doc_comment.not())
+        .then(any().and_is(just('\n').not()).repeated())
+        .ignored();
 
-    let comment = just("//").then(any().and_is(just('\n').not()).repeated());
+    // Block comments nest. An inner comment left open runs to the end of
+    // input, so only the outermost one reports the missing `*/`, at the
+    // span starting from its opening `/*`.
+    let block_comment = recursive(|block_comment| {
+        just("/*")
+            .ignore_then(
+                block_comment
+                    .ignored()
+                    .or(any().and_is(just("*/").not()).ignored())
+                    .repeated(),
+            )
+            .ignore_then(just("*/").to(true).or(end().to(false)))
+    })
+    .validate(|terminated, e, emitter| {
+        if !terminated {
+            emitter.emit(lex_error(LexErrorKind::UnterminatedBlockComment, e.span()));
+        }
+    });
+
+    // A `\` directly before a line break joins the two lines. Anywhere else
+    // it is still an unexpected character.
+    let continuation = just('\\').then(just("\r\n").or(just("\n"))).ignored();
 
+    let comment = line_comment.or(block_comment);
+    let trivia = comment.or(continuation).padded().repeated();
+
+    // Trailing trivia is consumed after the last token too, so empty,
+    // whitespace-only and comment-only sources all lex to no tokens.
     token
-        .map_with_span(|tok, span| (tok, span))
-        .padded_by(comment.repeated())
+        .map_with(|tok, e| {
+            let span: SimpleSpan = e.span();
+            Some((tok, span.into_range()))
+        })
+        .or(unexpected.to(None))
+        .padded_by(trivia.clone())
         .padded()
         .repeated()
-        .collect()
+        .collect::<Vec<_>>()
+        .map(|tokens| tokens.into_iter().flatten().collect())
+        .then_ignore(trivia)
+        .padded()
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[non_exhaustive]
 pub enum Op {
     Add,
     Sub,
     Mul,
     Div,
+    /// Truncated remainder: the quotient is rounded toward zero, so the
+    /// result takes the sign of the dividend and `-7 % 3` is `-1`.
+    Rem,
+    /// Exponentiation (`**`), right-associative: `2 ** 3 ** 2` is `2 ** (3 ** 2)`.
+    Pow,
     Eq,
     NotEq,
+    And,
+    Or,
+}
+
+impl std::fmt::Display for Op {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Op::Add => write!(f, "+"),
+            Op::Sub => write!(f, "-"),
+            Op::Mul => write!(f, "*"),
+            Op::Div => write!(f, "/"),
+            Op::Rem => write!(f, "%"),
+            Op::Pow => write!(f, "**"),
+            Op::Eq => write!(f, "=="),
+            Op::NotEq => write!(f, "!="),
+            Op::And => write!(f, "&&"),
+            Op::Or => write!(f, "||"),
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn lex(src: &str) -> Vec<Token<'_>> {
+        let tokens = lexer().parse(src).into_output().unwrap();
+        tokens.into_iter().map(|(tok, _span)| tok).collect()
+    }
+
     #[test]
     fn lex_simple_let_statement() {
-        let src = "let a = 5;";
+        assert_eq!(
+            lex("let a = 5;"),
+            vec![
+                Token::Let,
+                Token::Ident("a".into()),
+                Token::Op("=".into()),
+                Token::Num("5".into()),
+                Token::Ctrl(';'),
+            ]
+        );
+    }
+
+    #[test]
+    fn lex_struct_declaration_and_literal() {
+        assert_eq!(
+            lex("struct Point\n    x: i32\n\nlet p = Point { x: 1 };"),
+            vec![
+                Token::Struct,
+                Token::Ident("Point".into()),
+                Token::Ident("x".into()),
+                Token::Ctrl(':'),
+                Token::Ident("i32".into()),
+                Token::Let,
+                Token::Ident("p".into()),
+                Token::Op("=".into()),
+                Token::Ident("Point".into()),
+                Token::Ctrl('{'),
+                Token::Ident("x".into()),
+                Token::Ctrl(':'),
+                Token::Num("1".into()),
+                Token::Ctrl('}'),
+                Token::Ctrl(';'),
+            ]
+        );
+    }
+
+    #[test]
+    fn lex_logical_operators() {
+        assert_eq!(
+            lex("a == b && c || d"),
+            vec![
+                Token::Ident("a".into()),
+                Token::Op("==".into()),
+                Token::Ident("b".into()),
+                Token::Op("&&".into()),
+                Token::Ident("c".into()),
+                Token::Op("||".into()),
+                Token::Ident("d".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn lex_logical_operators_before_prefix_operators() {
+        assert_eq!(
+            lex("a&&!b"),
+            vec![
+                Token::Ident("a".into()),
+                Token::Op("&&".into()),
+                Token::Op("!".into()),
+                Token::Ident("b".into()),
+            ]
+        );
+        assert_eq!(
+            lex("a||-b"),
+            vec![
+                Token::Ident("a".into()),
+                Token::Op("||".into()),
+                Token::Op("-".into()),
+                Token::Ident("b".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn lex_modulo_operator() {
+        assert_eq!(
+            lex("1 + 10 % 3"),
+            vec![
+                Token::Num("1".into()),
+                Token::Op("+".into()),
+                Token::Num("10".into()),
+                Token::Op("%".into()),
+                Token::Num("3".into()),
+            ]
+        );
+        assert_eq!(
+            lex("10%-3"),
+            vec![
+                Token::Num("10".into()),
+                Token::Op("%".into()),
+                Token::Op("-".into()),
+                Token::Num("3".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn lex_power_operator_as_single_token() {
+        assert_eq!(
+            lex("2 ** 3 * 2"),
+            vec![
+                Token::Num("2".into()),
+                Token::Op("**".into()),
+                Token::Num("3".into()),
+                Token::Op("*".into()),
+                Token::Num("2".into()),
+            ]
+        );
+        assert_eq!(
+            lex("2**-1"),
+            vec![
+                Token::Num("2".into()),
+                Token::Op("**".into()),
+                Token::Op("-".into()),
+                Token::Num("1".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn lex_skips_comments_across_lines() {
+        assert_eq!(
+            lex("// a\n// b\nlet x // c\n// d\n"),
+            vec![Token::Let, Token::Ident("x".into())]
+        );
+    }
+
+    #[test]
+    fn lex_skips_nested_block_comments() {
+        assert_eq!(
+            lex("let /* outer /* inner */ still comment */ x"),
+            vec![Token::Let, Token::Ident("x".into())]
+        );
+    }
+
+    #[test]
+    fn lex_comment_directly_after_operator() {
+        assert_eq!(
+            lex("1+/* c */2"),
+            vec![
+                Token::Num("1".into()),
+                Token::Op("+".into()),
+                Token::Num("2".into()),
+            ]
+        );
+        assert_eq!(
+            lex("x=// c\ny"),
+            vec![
+                Token::Ident("x".into()),
+                Token::Op("=".into()),
+                Token::Ident("y".into()),
+            ]
+        );
+        assert_eq!(lex("a//c"), vec![Token::Ident("a".into())]);
+    }
+
+    #[test]
+    fn lex_block_comment_ending_at_start_of_next_line() {
+        let tokens = lexer()
+            .parse("let a /* starts here\n*/ = 5")
+            .into_output()
+            .unwrap();
+
+        assert_eq!(
+            tokens,
+            vec![
+                (Token::Let, 0..3),
+                (Token::Ident("a".into()), 4..5),
+                (Token::Op("=".into()), 24..25),
+                (Token::Num("5".into()), 26..27),
+            ]
+        );
+    }
+
+    #[test]
+    fn lex_unterminated_block_comment_reports_its_start() {
+        let (tokens, errors) = lexer()
+            .parse("let x /* open /* nested */\nlet y")
+            .into_output_errors();
+
+        assert_eq!(
+            tokens.unwrap(),
+            vec![(Token::Let, 0..3), (Token::Ident("x".into()), 4..5)]
+        );
+        assert_eq!(
+            errors,
+            vec![LexError {
+                kind: LexErrorKind::UnterminatedBlockComment,
+                span: 6..32,
+            }]
+        );
+    }
+
+    #[test]
+    fn lex_doc_comments_as_tokens() {
+        assert_eq!(
+            lex("/// Adds two numbers.\n///\n//// not a doc comment\n// nor this\nfn add"),
+            vec![
+                Token::DocComment(" Adds two numbers.".into()),
+                Token::DocComment("".into()),
+                Token::Fn,
+                Token::Ident("add".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn lex_empty_whitespace_and_comment_only_sources() {
+        for src in [
+            "",
+            "  \n\t\n",
+            "// only a comment\n",
+            "/* block */",
+            "// a\n\n/* b */\n",
+        ] {
+            let (tokens, errors) = lexer().parse(src).into_output_errors();
+
+            assert_eq!(tokens, Some(vec![]), "source: {src:?}");
+            assert!(errors.is_empty(), "source: {src:?}");
+        }
+    }
+
+    #[test]
+    fn token_spans_slice_their_source_text() {
+        let src = "/// Entry point.\nfn main() {\n    let greeting = \"hi\"; // note\n    /* x */ print(greeting, 3.14 != null);\n}\n";
         let tokens = lexer().parse(src).into_output().unwrap();
-        let tokens: Vec<_> = tokens.into_iter().map(|(tok, _span)| tok).collect();
+
+        assert_eq!(tokens.len(), 21);
+        for (tok, span) in tokens {
+            let expected = match &tok {
+                Token::Str(s) => format!("\"{s}\""),
+                tok => tok.to_string(),
+            };
+            assert_eq!(&src[span], expected);
+        }
+    }
+
+    #[test]
+    fn lex_unterminated_string_reports_its_start() {
+        let (tokens, errors) = lexer().parse("let s = \"abc").into_output_errors();
+
+        assert_eq!(
+            tokens.unwrap().last(),
+            Some(&(Token::Str("abc".into()), 8..12))
+        );
+        assert_eq!(
+            errors,
+            vec![LexError {
+                kind: LexErrorKind::UnterminatedString,
+                span: 8..12,
+            }]
+        );
+    }
+
+    #[test]
+    fn lex_unexpected_char_is_reported_and_skipped() {
+        let (tokens, errors) = lexer().parse("let x = 5 @ y").into_output_errors();
+        let tokens: Vec<_> = tokens
+            .unwrap()
+            .into_iter()
+            .map(|(tok, _span)| tok)
+            .collect();
 
         assert_eq!(
             tokens,
             vec![
                 Token::Let,
-                Token::Ident("a".to_string()),
-                Token::Op("=".to_string()),
-                Token::Num("5".to_string()),
-                Token::Ctrl(';'),
+                Token::Ident("x".into()),
+                Token::Op("=".into()),
+                Token::Num("5".into()),
+                Token::Ident("y".into()),
             ]
         );
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].kind, LexErrorKind::UnexpectedChar('@'));
+        assert_eq!(errors[0].to_string(), "unexpected character '@' at 10..11");
+    }
+
+    #[test]
+    fn lex_trailing_unexpected_chars_keep_earlier_tokens() {
+        let (tokens, errors) = lexer().parse("x @@").into_output_errors();
+
+        assert_eq!(tokens, Some(vec![(Token::Ident("x".into()), 0..1)]));
+        assert_eq!(
+            errors.iter().map(|e| e.span.clone()).collect::<Vec<_>>(),
+            vec![2..3, 3..4]
+        );
     }
-} 
\ No newline at end of file
+
+    #[test]
+    fn tokens_borrow_from_the_source() {
+        let src = String::from("let greeting = \"hi\"");
+        let tokens = lex(&src);
+
+        for tok in &tokens {
+            if let Token::Ident(text) | Token::Str(text) | Token::Op(text) = tok {
+                assert!(matches!(text, Cow::Borrowed(_)), "{tok:?} was copied");
+            }
+        }
+
+        let owned: Vec<Token<'static>> = tokens.into_iter().map(Token::into_owned).collect();
+        drop(src);
+        assert_eq!(owned[1], Token::Ident("greeting".into()));
+    }
+
+    #[test]
+    fn lex_for_in_keywords() {
+        assert_eq!(
+            lex("for item in items"),
+            vec![
+                Token::For,
+                Token::Ident("item".into()),
+                Token::In,
+                Token::Ident("items".into()),
+            ]
+        );
+        assert_eq!(
+            lex("format inner"),
+            vec![Token::Ident("format".into()), Token::Ident("inner".into())]
+        );
+    }
+
+    #[test]
+    fn lex_range_operators() {
+        assert_eq!(
+            lex("0..=10 1..n a.b 1.5"),
+            vec![
+                Token::Num("0".into()),
+                Token::Op("..=".into()),
+                Token::Num("10".into()),
+                Token::Num("1".into()),
+                Token::Op("..".into()),
+                Token::Ident("n".into()),
+                Token::Ident("a".into()),
+                Token::Ctrl('.'),
+                Token::Ident("b".into()),
+                Token::Num("1.5".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn lex_mut_keyword() {
+        assert_eq!(
+            lex("let mut counter = 0"),
+            vec![
+                Token::Let,
+                Token::Mut,
+                Token::Ident("counter".into()),
+                Token::Op("=".into()),
+                Token::Num("0".into()),
+            ]
+        );
+        assert_eq!(lex("mutable"), vec![Token::Ident("mutable".into())]);
+    }
+
+    #[test]
+    fn lex_const_keyword() {
+        assert_eq!(
+            lex("const SIZE = 4 * 1024"),
+            vec![
+                Token::Const,
+                Token::Ident("SIZE".into()),
+                Token::Op("=".into()),
+                Token::Num("4".into()),
+                Token::Op("*".into()),
+                Token::Num("1024".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn lex_import_keyword() {
+        assert_eq!(
+            lex("import utils"),
+            vec![Token::Import, Token::Ident("utils".into())]
+        );
+        assert_eq!(lex("imports"), vec![Token::Ident("imports".into())]);
+    }
+
+    #[test]
+    fn lex_enum_declaration_and_variant_reference() {
+        assert_eq!(
+            lex("enum Color\n    Red\n    Green\nColor.Red"),
+            vec![
+                Token::Enum,
+                Token::Ident("Color".into()),
+                Token::Ident("Red".into()),
+                Token::Ident("Green".into()),
+                Token::Ident("Color".into()),
+                Token::Ctrl('.'),
+                Token::Ident("Red".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn lex_match_arms() {
+        assert_eq!(
+            lex("match n\n    0 => \"zero\"\n    _ => n == 1"),
+            vec![
+                Token::Match,
+                Token::Ident("n".into()),
+                Token::Num("0".into()),
+                Token::Op("=>".into()),
+                Token::Str("zero".into()),
+                Token::Ident("_".into()),
+                Token::Op("=>".into()),
+                Token::Ident("n".into()),
+                Token::Op("==".into()),
+                Token::Num("1".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn wrapped_expressions_lex_like_single_lines() {
+        assert_eq!(lex("foo(\n  a,\n  b\n)\n"), lex("foo(a, b)"));
+        assert_eq!(lex("let x = (1 +\n    2) * 3"), lex("let x = (1 + 2) * 3"));
+    }
+
+    #[test]
+    fn lex_line_continuation() {
+        assert_eq!(
+            lex("let total = 1 + \\\n    2 + \\\r\n    3"),
+            lex("let total = 1 + 2 + 3")
+        );
+
+        let src = "let x = \\\n  y";
+        let tokens = lexer().parse(src).into_output().unwrap();
+        let index = crate::source::LineIndex::new(src);
+        assert_eq!(index.line_col(tokens[3].1.start), (2, 3));
+    }
+
+    #[test]
+    fn lex_stray_backslash_is_an_error() {
+        let (tokens, errors) = lexer().parse("a \\ b").into_output_errors();
+
+        assert_eq!(
+            tokens.unwrap(),
+            vec![
+                (Token::Ident("a".into()), 0..1),
+                (Token::Ident("b".into()), 4..5),
+            ]
+        );
+        assert_eq!(
+            errors,
+            vec![LexError {
+                kind: LexErrorKind::UnexpectedChar('\\'),
+                span: 2..3,
+            }]
+        );
+    }
+
+    #[test]
+    fn adversarial_inputs_terminate_with_ordered_spans() {
+        let inputs = [
+            "/*".repeat(1000),
+            "\"".repeat(1001),
+            "///".repeat(500),
+            "é@\u{0}\t\r#".repeat(200),
+            "1..=..2.3.4".to_string(),
+        ];
+
+        for src in &inputs {
+            let (tokens, errors) = lexer().parse(src).into_output_errors();
+            let tokens = tokens.unwrap_or_default();
+
+            assert!(tokens.len() <= src.len());
+            let mut end = 0;
+            for (_, span) in &tokens {
+                assert!(end <= span.start && span.start < span.end && span.end <= src.len());
+                end = span.end;
+            }
+            assert!(errors.iter().all(|e| e.span.end <= src.len()));
+        }
+    }
+
+    #[test]
+    fn token_categories_describe_tokens_for_users() {
+        let described: Vec<_> = lex("let x = \"s\" 1 true null ( /// d")
+            .iter()
+            .map(|tok| tok.category().to_string())
+            .collect();
+
+        assert_eq!(
+            described,
+            [
+                "keyword 'let'",
+                "an identifier",
+                "an operator",
+                "a string literal",
+                "a number literal",
+                "a boolean literal",
+                "'null'",
+                "punctuation",
+                "a doc comment",
+            ]
+        );
+    }
+
+    #[test]
+    fn category_matches_any_payload_but_tokens_compare_by_value() {
+        for tok in lex("x snake_case é _ Ident") {
+            assert_eq!(tok.category(), TokenCategory::Ident, "{tok:?}");
+        }
+        assert_ne!(Token::Ident("".into()), Token::Ident("x".into()));
+
+        let ops = lex("= ==");
+        assert_eq!(ops[0].category(), ops[1].category());
+        assert_ne!(ops[0], ops[1]);
+    }
+
+    #[test]
+    fn lex_raw_strings() {
+        let tokens = lexer()
+            .parse(r##"r"C:\new\dir" r#"say "hi""# r radius"##)
+            .into_output()
+            .unwrap();
+
+        assert_eq!(
+            tokens,
+            vec![
+                (Token::Str(r"C:\new\dir".into()), 0..13),
+                (Token::Str(r#"say "hi""#.into()), 14..27),
+                (Token::Ident("r".into()), 28..29),
+                (Token::Ident("radius".into()), 30..36),
+            ]
+        );
+    }
+
+    #[test]
+    fn lex_unterminated_raw_string_reports_its_start() {
+        let (_, errors) = lexer().parse("let p = r#\"abc\" def").into_output_errors();
+
+        assert_eq!(
+            errors,
+            vec![LexError {
+                kind: LexErrorKind::UnterminatedString,
+                span: 8..19,
+            }]
+        );
+    }
+
+    #[test]
+    fn lex_triple_quoted_string_keeps_newlines_and_indentation() {
+        let src = "let usage = \"\"\"\n  run \"file\"\n\"\"\"\nprint(usage)";
+        let tokens = lexer().parse(src).into_output().unwrap();
+
+        assert_eq!(tokens[3], (Token::Str("\n  run \"file\"\n".into()), 12..32));
+
+        let index = crate::source::LineIndex::new(src);
+        let (print, span) = &tokens[4];
+        assert_eq!(*print, Token::Ident("print".into()));
+        assert_eq!(index.line_col(span.start), (4, 1));
+    }
+
+    #[test]
+    fn lex_unterminated_triple_quoted_string_reports_its_start() {
+        let (_, errors) = lexer()
+            .parse("let s = \"\"\"open\n\"still open")
+            .into_output_errors();
+
+        assert_eq!(
+            errors,
+            vec![LexError {
+                kind: LexErrorKind::UnterminatedString,
+                span: 8..27,
+            }]
+        );
+    }
+}
@@ -0,0 +1,105 @@
+use crate::parser::{Expr, Val};
+use std::fmt::Write;
+
+/// Renders `expr` as a compact, single-line S-expression, e.g.
+/// `(let x (num 5) (call print (+ x (num 1))))`. Unlike the `Debug` output
+/// this is stable and short enough for golden-file tests.
+pub fn to_sexpr(expr: &Expr) -> String {
+    let mut out = String::new();
+    write_expr(&mut out, expr);
+    out
+}
+
+fn write_expr(out: &mut String, expr: &Expr) {
+    match expr {
+        Expr::Error => out.push_str("(error)"),
+        Expr::Value(val) => write_value(out, val),
+        Expr::Local(name) => out.push_str(name),
+        Expr::List(items) => write_list(out, "list", items.iter()),
+        Expr::Let(name, value, body) => {
+            write!(out, "(let {name} ").unwrap();
+            write_expr(out, value);
+            out.push(' ');
+            write_expr(out, body);
+            out.push(')');
+        }
+        Expr::Then(first, second) => write_list(out, "then", [&**first, &**second].into_iter()),
+        Expr::Binary(lhs, op, rhs) => {
+            write_list(out, &op.to_string(), [&**lhs, &**rhs].into_iter())
+        }
+        Expr::Call(callee, args) => {
+            out.push_str("(call ");
+            write_expr(out, callee);
+            for arg in args {
+                out.push(' ');
+                write_expr(out, arg);
+            }
+            out.push(')');
+        }
+        Expr::If(cond, then, otherwise) => {
+            write_list(out, "if", [&**cond, &**then, &**otherwise].into_iter())
+        }
+    }
+}
+
+fn write_list<'a>(out: &mut String, head: &str, items: impl Iterator<Item = &'a Expr>) {
+    out.push('(');
+    out.push_str(head);
+    for item in items {
+        out.push(' ');
+        write_expr(out, item);
+    }
+    out.push(')');
+}
+
+fn write_value(out: &mut String, val: &Val) {
+    match val {
+        Val::Null => out.push_str("null"),
+        Val::Bool(b) => write!(out, "(bool {b})").unwrap(),
+        Val::Num(n) => write!(out, "(num {n})").unwrap(),
+        Val::Str(s) => write!(out, "(str {s:?})").unwrap(),
+        Val::Func(name) => write!(out, "(fn {name})").unwrap(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Op;
+    use crate::test_util::{binary, call, let_, local, num};
+
+    #[test]
+    fn let_with_call_body() {
+        let expr = let_(
+            "x",
+            num(5.0),
+            call(local("print"), vec![binary(local("x"), Op::Add, num(1.5))]),
+        );
+
+        assert_eq!(
+            to_sexpr(&expr),
+            "(let x (num 5) (call print (+ x (num 1.5))))"
+        );
+    }
+
+    #[test]
+    fn every_node_kind() {
+        let expr = Expr::Then(
+            Box::new(Expr::If(
+                Box::new(binary(local("a"), Op::NotEq, Expr::Value(Val::Null))),
+                Box::new(Expr::List(vec![
+                    Expr::Value(Val::Str("say \"hi\"".to_string())),
+                    Expr::Value(Val::Bool(true)),
+                    Expr::Value(Val::Func("main".to_string())),
+                ])),
+                Box::new(Expr::Error),
+            )),
+            Box::new(call(local("done"), vec![])),
+        );
+
+        assert_eq!(
+            to_sexpr(&expr),
+            r#"(then (if (!= a null) (list (str "say \"hi\"") (bool true) (fn main)) (error)) (call done))"#
+        );
+    }
+}
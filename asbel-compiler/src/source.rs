@@ -0,0 +1,142 @@
+use crate::parser::Span;
+
+/// Line-start offsets precomputed over a source string, so diagnostics can
+/// quote the line a span points into without rescanning the input.
+#[derive(Clone, Debug)]
+pub struct LineIndex<'a> {
+    source: &'a str,
+    line_starts: Vec<usize>,
+}
+
+impl<'a> LineIndex<'a> {
+    pub fn new(source: &'a str) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(source.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+
+        Self {
+            source,
+            line_starts,
+        }
+    }
+
+    pub fn source(&self) -> &'a str {
+        self.source
+    }
+
+    /// 1-based line number containing the byte `offset`.
+    pub fn line_of(&self, offset: usize) -> usize {
+        self.line_starts.partition_point(|&start| start <= offset)
+    }
+
+    /// 1-based line and column of the byte `offset`. Columns count Unicode
+    /// scalar values (`char`s), not bytes, so `é` advances the column by one.
+    ///
+    /// Like [`LineIndex::snippet`], this never panics on a stale span: an
+    /// offset past the end is clamped to the end of the source, and one
+    /// inside a multi-byte char is rounded down to the start of that char.
+    pub fn line_col(&self, offset: usize) -> (usize, usize) {
+        let mut offset = offset.min(self.source.len());
+        while !self.source.is_char_boundary(offset) {
+            offset -= 1;
+        }
+        let line = self.line_of(offset);
+        let start = self.line_starts[line - 1];
+        let col = self.source[start..offset].chars().count() + 1;
+        (line, col)
+    }
+
+    /// Text of the 1-based `line`, without its line terminator.
+    pub fn line_text(&self, line: usize) -> Option<&'a str> {
+        let start = *self.line_starts.get(line.checked_sub(1)?)?;
+        let end = self
+            .line_starts
+            .get(line)
+            .map_or(self.source.len(), |&next| next - 1);
+        let text = &self.source[start..end];
+        Some(text.strip_suffix('\r').unwrap_or(text))
+    }
+
+    /// Text of the line on which `span` starts.
+    pub fn snippet(&self, span: &Span) -> Option<&'a str> {
+        self.line_text(self.line_of(span.start))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::lexer;
+    use chumsky::Parser;
+
+    const SRC: &str = "let a = 5;\r\nlet b = a;\nprint(b)";
+
+    #[test]
+    fn line_text_for_first_middle_and_last_line() {
+        let index = LineIndex::new(SRC);
+
+        assert_eq!(index.line_text(1), Some("let a = 5;"));
+        assert_eq!(index.line_text(2), Some("let b = a;"));
+        assert_eq!(index.line_text(3), Some("print(b)"));
+        assert_eq!(index.line_text(0), None);
+        assert_eq!(index.line_text(4), None);
+    }
+
+    #[test]
+    fn snippet_quotes_the_line_a_span_starts_on() {
+        let index = LineIndex::new(SRC);
+
+        assert_eq!(index.snippet(&(0..3)), Some("let a = 5;"));
+        assert_eq!(index.snippet(&(20..21)), Some("let b = a;"));
+        assert_eq!(index.snippet(&(SRC.len()..SRC.len())), Some("print(b)"));
+    }
+
+    #[test]
+    fn trailing_newline_starts_an_empty_last_line() {
+        let index = LineIndex::new("let a = 5;\n");
+
+        assert_eq!(index.line_text(2), Some(""));
+        assert_eq!(index.line_of(11), 2);
+    }
+
+    #[test]
+    fn line_col_of_every_token_counts_chars() {
+        let src = "let é = \"naïve\";\nlet b = é;\n  print(b)";
+        let index = LineIndex::new(src);
+        let tokens = lexer().parse(src).into_output().unwrap();
+        let positions: Vec<_> = tokens
+            .iter()
+            .map(|(tok, span)| (tok.to_string(), index.line_col(span.start)))
+            .collect();
+
+        let expected = [
+            ("let", (1, 1)),
+            ("é", (1, 5)),
+            ("=", (1, 7)),
+            ("naïve", (1, 9)),
+            (";", (1, 16)),
+            ("let", (2, 1)),
+            ("b", (2, 5)),
+            ("=", (2, 7)),
+            ("é", (2, 9)),
+            (";", (2, 10)),
+            ("print", (3, 3)),
+            ("(", (3, 8)),
+            ("b", (3, 9)),
+            (")", (3, 10)),
+        ];
+        assert_eq!(positions.len(), expected.len());
+        for ((text, pos), (expected_text, expected_pos)) in positions.iter().zip(expected) {
+            assert_eq!((text.as_str(), *pos), (expected_text, expected_pos));
+        }
+    }
+
+    #[test]
+    fn line_col_clamps_past_end_and_mid_char_offsets() {
+        let index = LineIndex::new("ab\né");
+
+        assert_eq!(index.line_col(99), (2, 2));
+        assert_eq!(index.line_col(4), (2, 1));
+        assert_eq!(LineIndex::new("ab").line_col(9), (1, 3));
+    }
+}
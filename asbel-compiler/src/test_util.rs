@@ -0,0 +1,24 @@
+//! AST constructors shared by the unit tests. Builders take children by
+//! value and box them, so test trees read like the source they stand for.
+
+use crate::parser::{Expr, Op, Val};
+
+pub(crate) fn num(n: f64) -> Expr {
+    Expr::Value(Val::Num(n))
+}
+
+pub(crate) fn local(name: &str) -> Expr {
+    Expr::Local(name.to_string())
+}
+
+pub(crate) fn let_(name: &str, value: Expr, body: Expr) -> Expr {
+    Expr::Let(name.to_string(), Box::new(value), Box::new(body))
+}
+
+pub(crate) fn binary(lhs: Expr, op: Op, rhs: Expr) -> Expr {
+    Expr::Binary(Box::new(lhs), op, Box::new(rhs))
+}
+
+pub(crate) fn call(callee: Expr, args: Vec<Expr>) -> Expr {
+    Expr::Call(Box::new(callee), args)
+}
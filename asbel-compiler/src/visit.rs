@@ -0,0 +1,234 @@
+use crate::parser::{Expr, Val};
+
+/// Read-only traversal of the AST. Every hook defaults to walking into the
+/// node's children, so an implementation only overrides the nodes it cares
+/// about and calls the matching `walk_*` function to keep descending.
+pub trait Visitor {
+    fn visit_expr(&mut self, expr: &Expr) {
+        walk_expr(self, expr);
+    }
+
+    fn visit_value(&mut self, _val: &Val) {}
+}
+
+/// Visits the children of `expr` in source order.
+pub fn walk_expr<V: Visitor + ?Sized>(visitor: &mut V, expr: &Expr) {
+    match expr {
+        Expr::Value(val) => visitor.visit_value(val),
+        Expr::List(items) => items.iter().for_each(|item| visitor.visit_expr(item)),
+        Expr::Let(_, value, body) => {
+            visitor.visit_expr(value);
+            visitor.visit_expr(body);
+        }
+        Expr::Then(first, second) => {
+            visitor.visit_expr(first);
+            visitor.visit_expr(second);
+        }
+        Expr::Binary(lhs, _, rhs) => {
+            visitor.visit_expr(lhs);
+            visitor.visit_expr(rhs);
+        }
+        Expr::Call(callee, args) => {
+            visitor.visit_expr(callee);
+            args.iter().for_each(|arg| visitor.visit_expr(arg));
+        }
+        Expr::If(cond, then, otherwise) => {
+            visitor.visit_expr(cond);
+            visitor.visit_expr(then);
+            visitor.visit_expr(otherwise);
+        }
+        Expr::Error | Expr::Local(_) => {}
+    }
+}
+
+/// In-place counterpart of [`Visitor`] for passes that rewrite nodes.
+pub trait VisitorMut {
+    fn visit_expr_mut(&mut self, expr: &mut Expr) {
+        walk_expr_mut(self, expr);
+    }
+
+    fn visit_value_mut(&mut self, _val: &mut Val) {}
+}
+
+/// Visits the children of `expr` mutably, in source order.
+pub fn walk_expr_mut<V: VisitorMut + ?Sized>(visitor: &mut V, expr: &mut Expr) {
+    match expr {
+        Expr::Value(val) => visitor.visit_value_mut(val),
+        Expr::List(items) => items
+            .iter_mut()
+            .for_each(|item| visitor.visit_expr_mut(item)),
+        Expr::Let(_, value, body) => {
+            visitor.visit_expr_mut(value);
+            visitor.visit_expr_mut(body);
+        }
+        Expr::Then(first, second) => {
+            visitor.visit_expr_mut(first);
+            visitor.visit_expr_mut(second);
+        }
+        Expr::Binary(lhs, _, rhs) => {
+            visitor.visit_expr_mut(lhs);
+            visitor.visit_expr_mut(rhs);
+        }
+        Expr::Call(callee, args) => {
+            visitor.visit_expr_mut(callee);
+            args.iter_mut().for_each(|arg| visitor.visit_expr_mut(arg));
+        }
+        Expr::If(cond, then, otherwise) => {
+            visitor.visit_expr_mut(cond);
+            visitor.visit_expr_mut(then);
+            visitor.visit_expr_mut(otherwise);
+        }
+        Expr::Error | Expr::Local(_) => {}
+    }
+}
+
+/// Rewriting traversal: each hook takes a node by value and returns its
+/// replacement. The defaults rebuild the node from its folded children, so
+/// a folder that overrides nothing returns an equal tree.
+pub trait Folder {
+    fn fold_expr(&mut self, expr: Expr) -> Expr {
+        fold_children(self, expr)
+    }
+
+    fn fold_value(&mut self, val: Val) -> Val {
+        val
+    }
+}
+
+/// Rebuilds `expr` with each child replaced by its folded form.
+pub fn fold_children<F: Folder + ?Sized>(folder: &mut F, expr: Expr) -> Expr {
+    let mut fold = |expr: Box<Expr>| Box::new(folder.fold_expr(*expr));
+    match expr {
+        Expr::Value(val) => Expr::Value(folder.fold_value(val)),
+        Expr::List(items) => Expr::List(items.into_iter().map(|e| folder.fold_expr(e)).collect()),
+        Expr::Let(name, value, body) => Expr::Let(name, fold(value), fold(body)),
+        Expr::Then(first, second) => Expr::Then(fold(first), fold(second)),
+        Expr::Binary(lhs, op, rhs) => Expr::Binary(fold(lhs), op, fold(rhs)),
+        Expr::Call(callee, args) => {
+            let callee = fold(callee);
+            let args = args.into_iter().map(|e| folder.fold_expr(e)).collect();
+            Expr::Call(callee, args)
+        }
+        Expr::If(cond, then, otherwise) => Expr::If(fold(cond), fold(then), fold(otherwise)),
+        expr @ (Expr::Error | Expr::Local(_)) => expr,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Op;
+
+    #[derive(Default)]
+    struct Collect {
+        locals: Vec<String>,
+        values: usize,
+    }
+
+    impl Visitor for Collect {
+        fn visit_expr(&mut self, expr: &Expr) {
+            if let Expr::Local(name) = expr {
+                self.locals.push(name.clone());
+            }
+            walk_expr(self, expr);
+        }
+
+        fn visit_value(&mut self, _val: &Val) {
+            self.values += 1;
+        }
+    }
+
+    fn call_with_nested_args() -> Expr {
+        // f(a, [b + 1], if c then d else 2)
+        Expr::Call(
+            Box::new(Expr::Local("f".to_string())),
+            vec![
+                Expr::Local("a".to_string()),
+                Expr::List(vec![Expr::Binary(
+                    Box::new(Expr::Local("b".to_string())),
+                    Op::Add,
+                    Box::new(Expr::Value(Val::Num(1.0))),
+                )]),
+                Expr::If(
+                    Box::new(Expr::Local("c".to_string())),
+                    Box::new(Expr::Local("d".to_string())),
+                    Box::new(Expr::Value(Val::Num(2.0))),
+                ),
+            ],
+        )
+    }
+
+    #[test]
+    fn walker_reaches_nodes_nested_in_call_arguments() {
+        let mut collect = Collect::default();
+        collect.visit_expr(&call_with_nested_args());
+
+        assert_eq!(collect.locals, ["f", "a", "b", "c", "d"]);
+        assert_eq!(collect.values, 2);
+    }
+
+    #[test]
+    fn mutable_walker_rewrites_nested_values() {
+        struct Double;
+
+        impl VisitorMut for Double {
+            fn visit_value_mut(&mut self, val: &mut Val) {
+                if let Val::Num(n) = val {
+                    *n *= 2.0;
+                }
+            }
+        }
+
+        let mut expr = call_with_nested_args();
+        Double.visit_expr_mut(&mut expr);
+
+        let Expr::Call(_, args) = &expr else {
+            unreachable!()
+        };
+        assert_eq!(
+            args[1],
+            Expr::List(vec![Expr::Binary(
+                Box::new(Expr::Local("b".to_string())),
+                Op::Add,
+                Box::new(Expr::Value(Val::Num(2.0))),
+            )])
+        );
+    }
+
+    #[test]
+    fn default_folder_is_the_identity() {
+        struct Identity;
+        impl Folder for Identity {}
+
+        assert_eq!(
+            Identity.fold_expr(call_with_nested_args()),
+            call_with_nested_args()
+        );
+    }
+
+    #[test]
+    fn folder_rewrites_nested_nodes() {
+        struct Rename;
+
+        impl Folder for Rename {
+            fn fold_expr(&mut self, expr: Expr) -> Expr {
+                match expr {
+                    Expr::Local(name) if name == "b" => Expr::Local("renamed".to_string()),
+                    expr => fold_children(self, expr),
+                }
+            }
+        }
+
+        let Expr::Call(_, args) = Rename.fold_expr(call_with_nested_args()) else {
+            unreachable!()
+        };
+        assert_eq!(
+            args[1],
+            Expr::List(vec![Expr::Binary(
+                Box::new(Expr::Local("renamed".to_string())),
+                Op::Add,
+                Box::new(Expr::Value(Val::Num(1.0))),
+            )])
+        );
+    }
+}
@@ -0,0 +1,64 @@
+//! Golden-file tests: every `tests/corpus/*.asb` file is lexed and the
+//! token stream and errors are compared against the `.snap` file next to
+//! it. Run with `UPDATE_SNAPSHOTS=1` to write the snapshots instead.
+
+use std::{env, fmt::Write, fs, path::Path};
+
+use asbel_compiler::parser::lexer;
+use chumsky::Parser;
+
+/// One `start..end Token` line per token, then one `error: ...` line per
+/// lex error.
+fn render(source: &str) -> String {
+    let (tokens, errors) = lexer().parse(source).into_output_errors();
+    let mut snapshot = String::new();
+    for (token, span) in tokens.unwrap_or_default() {
+        writeln!(snapshot, "{}..{} {:?}", span.start, span.end, token).unwrap();
+    }
+    for error in errors {
+        writeln!(snapshot, "error: {}", error).unwrap();
+    }
+    snapshot
+}
+
+#[test]
+fn corpus_matches_snapshots() {
+    let corpus = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/corpus");
+    let update = env::var_os("UPDATE_SNAPSHOTS").is_some_and(|v| v == "1");
+
+    let mut inputs: Vec<_> = fs::read_dir(&corpus)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "asb"))
+        .collect();
+    inputs.sort();
+    assert!(
+        !inputs.is_empty(),
+        "no corpus files in {}",
+        corpus.display()
+    );
+
+    let mut mismatched = Vec::new();
+    for input in inputs {
+        let actual = render(&fs::read_to_string(&input).unwrap());
+        let snap = input.with_extension("snap");
+        if update {
+            fs::write(&snap, actual).unwrap();
+            continue;
+        }
+        match fs::read_to_string(&snap) {
+            Ok(expected) if expected == actual => {}
+            Ok(expected) => {
+                mismatched.push(snap.display().to_string());
+                eprintln!("--- {}\n{}+++ actual\n{}", snap.display(), expected, actual);
+            }
+            Err(_) => mismatched.push(format!("{} (missing)", snap.display())),
+        }
+    }
+
+    assert!(
+        mismatched.is_empty(),
+        "snapshots differ, rerun with UPDATE_SNAPSHOTS=1 to accept: {:?}",
+        mismatched
+    );
+}
@@ -0,0 +1,142 @@
+//! Exercises the public surface from outside the crate and pins it: every
+//! public item, variant, field and method is listed in `public_api.txt`,
+//! so an accidental addition, removal, or enum losing `#[non_exhaustive]`
+//! fails here. Run with `UPDATE_SNAPSHOTS=1` to accept a deliberate change.
+
+use std::{env, fs, path::Path};
+
+use asbel_compiler::parser::{lexer, Span, Token};
+use chumsky::Parser;
+
+#[test]
+fn lexer_is_public_and_yields_spans() {
+    let tokens: Vec<(Token, Span)> = lexer().parse("let a = 5;").into_output().unwrap();
+    assert_eq!(tokens.len(), 5);
+    assert_eq!(tokens[0], (Token::Let, 0..3));
+}
+
+/// What a `{ ... }` block at the top of a module contributes to the list.
+enum Block {
+    Enum(String),
+    Struct(String),
+    Trait(String),
+    Impl(String),
+    Private,
+}
+
+fn ident(s: &str) -> &str {
+    let end = s
+        .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .unwrap_or(s.len());
+    &s[..end]
+}
+
+/// Lists the public items of one rustfmt-formatted module file. Items are
+/// read from column 0 and their members from the first indent level, which
+/// keeps `#[cfg(test)]` modules and function bodies out of the list.
+fn module_items(module: &str, source: &str, items: &mut Vec<String>) {
+    let prefix = if module.is_empty() {
+        String::new()
+    } else {
+        format!("{module}::")
+    };
+    let mut block = None;
+    let mut non_exhaustive = false;
+
+    for line in source.lines() {
+        if let Some(member) = line.strip_prefix("    ") {
+            match &block {
+                Some(Block::Enum(name)) if member.starts_with(char::is_uppercase) => {
+                    items.push(format!("variant {prefix}{name}::{}", ident(member)));
+                }
+                Some(Block::Struct(name)) => {
+                    if let Some(field) = member.strip_prefix("pub ") {
+                        items.push(format!("field {prefix}{name}::{}", ident(field)));
+                    }
+                }
+                Some(Block::Trait(name)) => {
+                    if let Some(method) = member.strip_prefix("fn ") {
+                        items.push(format!("method {prefix}{name}::{}", ident(method)));
+                    }
+                }
+                Some(Block::Impl(name)) => {
+                    if let Some(method) = member.strip_prefix("pub fn ") {
+                        items.push(format!("method {prefix}{name}::{}", ident(method)));
+                    }
+                }
+                _ => {}
+            }
+            continue;
+        }
+
+        if line == "#[non_exhaustive]" {
+            non_exhaustive = true;
+            continue;
+        }
+        if line.is_empty() || line.starts_with("//") || line.starts_with("#[") {
+            continue;
+        }
+        if line == "}" {
+            block = None;
+            continue;
+        }
+
+        let opens = line.ends_with('{');
+        if let Some(item) = line.strip_prefix("pub ") {
+            let (kind, rest) = item.split_once(' ').unwrap();
+            let name = ident(rest).to_string();
+            let attr = if non_exhaustive {
+                " #[non_exhaustive]"
+            } else {
+                ""
+            };
+            items.push(format!("{kind} {prefix}{name}{attr}"));
+            block = opens.then_some(match kind {
+                "enum" => Block::Enum(name),
+                "struct" => Block::Struct(name),
+                "trait" => Block::Trait(name),
+                _ => Block::Private,
+            });
+        } else if let Some(imp) = line.strip_prefix("impl") {
+            // Skip generics, then take the type name; trait impls add nothing.
+            let ty = match imp.strip_prefix('<') {
+                Some(generic) => generic.split_once("> ").unwrap().1,
+                None => imp.trim_start(),
+            };
+            block = Some(if imp.contains(" for ") {
+                Block::Private
+            } else {
+                Block::Impl(ident(ty).to_string())
+            });
+        } else if opens {
+            block = Some(Block::Private);
+        }
+        non_exhaustive = false;
+    }
+}
+
+#[test]
+fn public_surface_matches_snapshot() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let lib = fs::read_to_string(root.join("src/lib.rs")).unwrap();
+
+    let mut items = Vec::new();
+    module_items("", &lib, &mut items);
+    for module in lib.lines().filter_map(|l| l.strip_prefix("pub mod ")) {
+        let module = module.trim_end_matches(';');
+        let source = fs::read_to_string(root.join(format!("src/{module}.rs"))).unwrap();
+        module_items(module, &source, &mut items);
+    }
+    let actual = items.join("\n") + "\n";
+
+    let snapshot = root.join("tests/public_api.txt");
+    if env::var_os("UPDATE_SNAPSHOTS").is_some_and(|v| v == "1") {
+        fs::write(&snapshot, actual).unwrap();
+        return;
+    }
+    let expected = fs::read_to_string(&snapshot).unwrap_or_default();
+    assert!(
+        expected == actual,
+        "public API changed, rerun with UPDATE_SNAPSHOTS=1 to accept:\n--- expected\n{expected}+++ actual\n{actual}"
+    );
+}
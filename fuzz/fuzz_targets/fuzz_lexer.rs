@@ -0,0 +1,25 @@
+//! Run with `cargo +nightly fuzz run fuzz_lexer` from the repository root.
+
+#![no_main]
+
+use asbel_compiler::parser::lexer;
+use chumsky::Parser;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let src = String::from_utf8_lossy(data);
+    let (tokens, errors) = lexer().parse(&src).into_output_errors();
+
+    // Every token consumes at least one byte, so there can never be more
+    // tokens than input bytes, and spans must be in order and in bounds.
+    let tokens = tokens.unwrap_or_default();
+    assert!(tokens.len() <= src.len());
+    let mut end = 0;
+    for (_, span) in &tokens {
+        assert!(end <= span.start && span.start < span.end && span.end <= src.len());
+        end = span.end;
+    }
+    for error in &errors {
+        assert!(error.span.end <= src.len());
+    }
+});